@@ -6,10 +6,10 @@ use rustc_lint::{LateContext, LateLintPass};
 use rustc_session::{declare_lint_pass, declare_tool_lint};
 
 declare_clippy_lint! {
-    /// **What it does:*** Checks for unnecessary `ok()` in if let.
+    /// **What it does:*** Checks for unnecessary `ok()`/`err()` in `if let`/`while let`.
     ///
-    /// **Why is this bad?** Calling `ok()` in if let is unnecessary, instead match
-    /// on `Ok(pat)`
+    /// **Why is this bad?** Calling `ok()`/`err()` in `if let`/`while let` is unnecessary, instead
+    /// match on `Ok(pat)`/`Err(pat)`
     ///
     /// **Known problems:** None.
     ///
@@ -32,41 +32,65 @@ declare_clippy_lint! {
     /// ```
     pub IF_LET_SOME_RESULT,
     style,
-    "usage of `ok()` in `if let Some(pat)` statements is unnecessary, match on `Ok(pat)` instead"
+    "usage of `ok()`/`err()` in `if let Some(pat)` statements is unnecessary, match on `Ok(pat)`/`Err(pat)` instead"
 }
 
-declare_lint_pass!(OkIfLet => [IF_LET_SOME_RESULT]);
+declare_lint_pass!(MatchResultOk => [IF_LET_SOME_RESULT]);
 
-impl<'a, 'tcx> LateLintPass<'a, 'tcx> for OkIfLet {
-    fn check_expr(&mut self, cx: &LateContext<'a, 'tcx>, expr: &'tcx Expr<'_>) {
+impl<'a, 'tcx> MatchResultOk {
+    /// Checks a single `op.<method>()` call matched against `Some(pat)`, suggesting `<variant>(pat)` instead.
+    fn check_some_of_result(
+        &self,
+        cx: &LateContext<'a, 'tcx>,
+        expr: &'tcx Expr<'_>,
+        method: &str,
+        variant: &str,
+    ) -> bool {
         if_chain! { //begin checking variables
             if let ExprKind::Match(ref op, ref body, source) = expr.kind; //test if expr is a match
-            if let MatchSource::IfLetDesugar { .. } = source; //test if it is an If Let
-            if let ExprKind::MethodCall(_, ok_span, ref result_types) = op.kind; //check is expr.ok() has type Result<T,E>.ok()
+            if let MatchSource::IfLetDesugar { .. } | MatchSource::WhileLetDesugar { .. } = source; //test if it is an If Let or While Let
+            if let ExprKind::MethodCall(_, _, ref result_types) = op.kind; //check is expr.ok()/err() has type Result<T,E>.ok()/err()
             if let PatKind::TupleStruct(QPath::Resolved(_, ref x), ref y, _)  = body[0].pat.kind; //get operation
-            if method_chain_args(op, &["ok"]).is_some(); //test to see if using ok() methoduse std::marker::Sized;
+            if method_chain_args(op, &[method]).is_some(); //test to see if using ok()/err() method
             let is_result_type = match_type(cx, cx.tables.expr_ty(&result_types[0]), &paths::RESULT);
             if rustc_hir_pretty::to_string(rustc_hir_pretty::NO_ANN, |s| s.print_path(x, false)) == "Some" && is_result_type;
 
             then {
                 let mut applicability = Applicability::MachineApplicable;
+                // recover the `if`/`while` keyword from the original snippet instead of hard-coding
+                // it, so a `while let` lint doesn't get rewritten into an `if let` fix
+                let leading_snippet = snippet_with_applicability(cx, expr.span.until(op.span), "", &mut applicability);
+                let keyword = if leading_snippet.trim_start().starts_with("while") { "while" } else { "if" };
                 let some_expr_string = snippet_with_applicability(cx, y[0].span, "", &mut applicability);
-                let trimmed_ok = snippet_with_applicability(cx, op.span.until(ok_span), "", &mut applicability);
-                let sugg = format!(
-                    "if let Ok({}) = {}",
-                    some_expr_string,
-                    trimmed_ok.trim().trim_end_matches('.'),
-                );
+                // snippet the receiver of the `ok()`/`err()` call directly from its own span, so for a
+                // chain like `a.b().c().ok()` the suggestion uses exactly `a.b().c()`
+                let receiver_span = result_types[0].span;
+                let receiver_string = snippet_with_applicability(cx, receiver_span, "..", &mut applicability);
+                if receiver_span.from_expansion() || receiver_string.contains("//") || receiver_string.contains("/*") {
+                    applicability = Applicability::MaybeIncorrect;
+                }
+                let sugg = format!("{} let {}({}) = {}", keyword, variant, some_expr_string, receiver_string);
                 span_lint_and_sugg(
                     cx,
                     IF_LET_SOME_RESULT,
                     expr.span.with_hi(op.span.hi()),
-                    "Matching on `Some` with `ok()` is redundant",
-                    &format!("Consider matching on `Ok({})` and removing the call to `ok` instead", some_expr_string),
+                    &format!("Matching on `Some` with `{}()` is redundant", method),
+                    &format!("Consider matching on `{}({})` and removing the call to `{}` instead", variant, some_expr_string, method),
                     sugg,
                     applicability,
                 );
+                return true;
             }
         }
+        false
+    }
+}
+
+impl<'a, 'tcx> LateLintPass<'a, 'tcx> for MatchResultOk {
+    fn check_expr(&mut self, cx: &LateContext<'a, 'tcx>, expr: &'tcx Expr<'_>) {
+        if self.check_some_of_result(cx, expr, "ok", "Ok") {
+            return;
+        }
+        self.check_some_of_result(cx, expr, "err", "Err");
     }
 }